@@ -1,18 +1,85 @@
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use chrono::{Local, NaiveDate};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to parse JSON: {0}")]
+    Parse(#[from] serde_json::Error),
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct User {
     name: String,
     age: u32,
     sex: String,
     height: f32,
     weight: f32,
+    activity_level: ActivityLevel,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum ActivityLevel {
+    Sedentary,
+    Light,
+    Moderate,
+    Active,
+    VeryActive,
+}
+
+impl ActivityLevel {
+    fn factor(&self) -> f32 {
+        match self {
+            ActivityLevel::Sedentary => 1.2,
+            ActivityLevel::Light => 1.375,
+            ActivityLevel::Moderate => 1.55,
+            ActivityLevel::Active => 1.725,
+            ActivityLevel::VeryActive => 1.9,
+        }
+    }
+
+    fn from_choice(choice: &str) -> Option<ActivityLevel> {
+        match choice.trim() {
+            "1" => Some(ActivityLevel::Sedentary),
+            "2" => Some(ActivityLevel::Light),
+            "3" => Some(ActivityLevel::Moderate),
+            "4" => Some(ActivityLevel::Active),
+            "5" => Some(ActivityLevel::VeryActive),
+            _ => None,
+        }
+    }
+}
+
+fn bmr(user: &User) -> f32 {
+    // Mifflin-St Jeor equation
+    let base = 10.0 * user.weight + 6.25 * user.height - 5.0 * user.age as f32;
+    if user.sex.trim().eq_ignore_ascii_case("F") {
+        base - 161.0
+    } else {
+        base + 5.0
+    }
+}
+
+fn tdee(user: &User) -> f32 {
+    bmr(user) * user.activity_level.factor()
+}
+
+fn protein_target(user: &User) -> f32 {
+    0.8 * user.weight
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Product {
     unit: String,
     calories: f32,
@@ -20,27 +87,170 @@ struct Product {
     minerals: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Meal {
     name: String,
     items: Vec<(String, f32)>,
     servings: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct DailyLog {
     date: NaiveDate,
     meals: Vec<Meal>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProductRecord {
+    name: String,
+    #[serde(flatten)]
+    product: Product,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DailyLogRecord {
+    username: String,
+    date: NaiveDate,
+    meals: Vec<Meal>,
+}
+
+#[derive(Debug, Clone)]
+struct AppConfig {
+    nutrition_api_endpoint: String,
+    nutrition_cache_ttl_secs: u64,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            nutrition_api_endpoint: "https://api.nutrition.example/v1/lookup".to_string(),
+            nutrition_cache_ttl_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+static APP_CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+fn app_config() -> &'static AppConfig {
+    APP_CONFIG.get_or_init(load_config)
+}
+
+fn load_config() -> AppConfig {
+    let mut config = AppConfig::default();
+    if let Ok(data) = fs::read_to_string("config.txt") {
+        for line in data.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim() {
+                    "nutrition_api_endpoint" => {
+                        config.nutrition_api_endpoint = value.trim().to_string()
+                    }
+                    "nutrition_cache_ttl_secs" => {
+                        if let Ok(secs) = value.trim().parse() {
+                            config.nutrition_cache_ttl_secs = secs;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    config
+}
+
+#[derive(Debug, Clone)]
+enum Fetchable<T> {
+    None,
+    Fetched(T),
+}
+
+fn fetch_json(url: &str, query: &str, ttl_secs: u64) -> Option<Value> {
+    let cache_path = format!("cache_{}.json", sanitize_cache_key(query));
+
+    if let Some(body) = read_cached_body(&cache_path, ttl_secs) {
+        return Some(body);
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .ok()?;
+    let response = client
+        .get(url)
+        .query(&[("query", query)])
+        .send()
+        .ok()?;
+    let body: Value = response.json().ok()?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let cached = serde_json::json!({ "fetched_at": now, "body": body });
+    let _ = fs::write(&cache_path, cached.to_string());
+
+    Some(body)
+}
+
+fn read_cached_body(cache_path: &str, ttl_secs: u64) -> Option<Value> {
+    let data = fs::read_to_string(cache_path).ok()?;
+    let cached: Value = serde_json::from_str(&data).ok()?;
+    let fetched_at = cached.get("fetched_at").and_then(Value::as_u64).unwrap_or(0);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    if now.saturating_sub(fetched_at) < ttl_secs {
+        cached.get("body").cloned()
+    } else {
+        None
+    }
+}
+
+fn sanitize_cache_key(query: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    query.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn fetch_nutrition(product_name: &str) -> Fetchable<Product> {
+    let config = app_config();
+    match fetch_json(
+        &config.nutrition_api_endpoint,
+        product_name,
+        config.nutrition_cache_ttl_secs,
+    ) {
+        Some(body) => {
+            let unit = body.get("unit").and_then(Value::as_str).unwrap_or("g").to_string();
+            let calories = body.get("calories").and_then(Value::as_f64).unwrap_or(0.0) as f32;
+            let proteins = body.get("proteins").and_then(Value::as_f64).unwrap_or(0.0) as f32;
+            let minerals = body.get("minerals").and_then(Value::as_f64).unwrap_or(0.0) as f32;
+            Fetchable::Fetched(Product { unit, calories, proteins, minerals })
+        }
+        None => Fetchable::None,
+    }
+}
+
 fn main() {
-    let mut users: HashMap<String, User> = load_users();
-    let mut products: HashMap<String, Product> = load_products();
-    let mut meals: Vec<Meal> = load_meals();
-    let mut daily_logs: HashMap<(String, NaiveDate), DailyLog> = load_daily_logs();
+    app_config();
+    let mut users: HashMap<String, User> = load_users().unwrap_or_else(|e| {
+        eprintln!("Warning: could not load users.json ({e}), starting with no users.");
+        HashMap::new()
+    });
+    let mut products: HashMap<String, Product> = load_products().unwrap_or_else(|e| {
+        eprintln!("Warning: could not load products.json ({e}), starting with no products.");
+        HashMap::new()
+    });
+    let mut meals: Vec<Meal> = load_meals().unwrap_or_else(|e| {
+        eprintln!("Warning: could not load meals.json ({e}), starting with no meals.");
+        Vec::new()
+    });
+    let mut daily_logs: HashMap<(String, NaiveDate), DailyLog> = load_daily_logs().unwrap_or_else(|e| {
+        eprintln!("Warning: could not load daily_logs.json ({e}), starting with no daily logs.");
+        HashMap::new()
+    });
 
     println!("Welcome to Enhanced Diet Manager CLI");
-    
+
     loop {
         println!("1. Sign Up\n2. Log In\n3. Exit");
         let mut choice = String::new();
@@ -49,10 +259,18 @@ fn main() {
             "1" => sign_up(&mut users),
             "2" => log_in(&users, &mut products, &mut meals, &mut daily_logs),
             "3" => {
-                save_users(&users);
-                save_products(&products);
-                save_meals(&meals);
-                save_daily_logs(&daily_logs);
+                if let Err(e) = save_users(&users) {
+                    eprintln!("Warning: failed to save users: {e}");
+                }
+                if let Err(e) = save_products(&products) {
+                    eprintln!("Warning: failed to save products: {e}");
+                }
+                if let Err(e) = save_meals(&meals) {
+                    eprintln!("Warning: failed to save meals: {e}");
+                }
+                if let Err(e) = save_daily_logs(&daily_logs) {
+                    eprintln!("Warning: failed to save daily logs: {e}");
+                }
                 println!("Exiting... Goodbye!");
                 break;
             }
@@ -86,15 +304,23 @@ fn sign_up(users: &mut HashMap<String, User>) {
     io::stdin().read_line(&mut weight).unwrap();
     let weight: f32 = weight.trim().parse().unwrap_or(0.0);
 
+    println!("Enter Activity Level (1=Sedentary, 2=Light, 3=Moderate, 4=Active, 5=Very Active):");
+    let mut activity_choice = String::new();
+    io::stdin().read_line(&mut activity_choice).unwrap();
+    let activity_level = ActivityLevel::from_choice(&activity_choice).unwrap_or(ActivityLevel::Sedentary);
+
     let user = User {
         name: name.trim().to_string(),
         age,
         sex: sex.trim().to_string(),
         height,
         weight,
+        activity_level,
     };
     users.insert(name.trim().to_string(), user);
-    save_users(users);
+    if let Err(e) = save_users(users) {
+        eprintln!("Warning: failed to save users: {e}");
+    }
     println!("User registered successfully!");
 }
 
@@ -129,15 +355,15 @@ fn log_in(users: &HashMap<String, User>, products: &mut HashMap<String, Product>
     io::stdin().read_line(&mut name).unwrap();
     let name = name.trim().to_string();
 
-    if users.contains_key(&name) {
+    if let Some(user) = users.get(&name) {
         println!("Welcome back, {}!", name);
-        user_menu(&name, products, meals, daily_logs);
+        user_menu(user, products, meals, daily_logs);
     } else {
         println!("User not found.");
     }
 }
 
-fn user_menu(username: &str, products: &mut HashMap<String, Product>, meals: &mut Vec<Meal>, daily_logs: &mut HashMap<(String, NaiveDate), DailyLog>) {
+fn user_menu(user: &User, products: &mut HashMap<String, Product>, meals: &mut Vec<Meal>, daily_logs: &mut HashMap<(String, NaiveDate), DailyLog>) {
     loop {
         println!("\nUser Menu:");
         println!("1. Manage Products");
@@ -152,8 +378,8 @@ fn user_menu(username: &str, products: &mut HashMap<String, Product>, meals: &mu
         match choice.trim() {
             "1" => manage_products(products),
             "2" => manage_meals(meals, products),
-            "3" => log_daily_intake(username, products, meals, daily_logs),
-            "4" => view_daily_logs(username, daily_logs, products),
+            "3" => log_daily_intake(&user.name, products, meals, daily_logs),
+            "4" => view_daily_logs(user, daily_logs, products),
             "5" => break,
             _ => println!("Invalid choice. Try again."),
         }
@@ -232,6 +458,36 @@ fn add_product(products: &mut HashMap<String, Product>) {
     io::stdin().read_line(&mut name).unwrap();
     let name = name.trim().to_string();
 
+    println!("Fetch from database? (y/n):");
+    let mut fetch_choice = String::new();
+    io::stdin().read_line(&mut fetch_choice).unwrap();
+
+    let product = if fetch_choice.trim().eq_ignore_ascii_case("y") {
+        match fetch_nutrition(&name) {
+            Fetchable::Fetched(product) => {
+                println!(
+                    "Fetched: {} {}, {} cal, {} proteins, {} minerals",
+                    product.unit, name, product.calories, product.proteins, product.minerals
+                );
+                product
+            }
+            Fetchable::None => {
+                println!("Lookup failed, falling back to manual entry.");
+                read_product_manually()
+            }
+        }
+    } else {
+        read_product_manually()
+    };
+
+    products.insert(name.clone(), product);
+    if let Err(e) = save_products(products) {
+        eprintln!("Warning: failed to save products: {e}");
+    }
+    println!("Product '{}' added successfully!", name);
+}
+
+fn read_product_manually() -> Product {
     println!("Enter unit (slice/cup/g/100g/tablespoon/teaspoon):");
     let mut unit = String::new();
     io::stdin().read_line(&mut unit).unwrap();
@@ -246,17 +502,96 @@ fn add_product(products: &mut HashMap<String, Product>) {
     println!("Enter minerals:");
     let minerals: f32 = get_float_input("Invalid minerals, using 0");
 
-    let product = Product { unit, calories, proteins, minerals };
-    products.insert(name.clone(), product);
-    save_products(products);
-    println!("Product '{}' added successfully!", name);
+    Product { unit, calories, proteins, minerals }
 }
 
-fn add_meal(products: &HashMap<String, Product>, meals: &mut Vec<Meal>) {
-    println!("Enter meal name:");
-    let mut meal_name = String::new();
-    io::stdin().read_line(&mut meal_name).unwrap();
-    let meal_name = meal_name.trim().to_string();
+fn normalize_unit(unit: &str) -> String {
+    match unit.to_lowercase().as_str() {
+        "g" | "gram" | "grams" => "g".to_string(),
+        "100g" => "100g".to_string(),
+        "tbsp" | "tablespoon" | "tablespoons" => "tablespoon".to_string(),
+        "tsp" | "teaspoon" | "teaspoons" => "teaspoon".to_string(),
+        "cup" | "cups" => "cup".to_string(),
+        "slice" | "slices" => "slice".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn grams_per_unit(unit: &str) -> Option<f32> {
+    // rough dry-ingredient conversions, not precise for every product
+    match unit {
+        "g" => Some(1.0),
+        "100g" => Some(100.0),
+        "tablespoon" => Some(15.0),
+        "teaspoon" => Some(5.0),
+        "cup" => Some(240.0),
+        "slice" => Some(30.0),
+        _ => None,
+    }
+}
+
+fn convert_quantity(quantity: f32, from_unit: &str, to_unit: &str) -> Option<f32> {
+    if from_unit == to_unit {
+        return Some(quantity);
+    }
+    let from_grams = grams_per_unit(from_unit)?;
+    let to_grams = grams_per_unit(to_unit)?;
+    Some(quantity * from_grams / to_grams)
+}
+
+fn parse_recipe_line(line: &str, products: &HashMap<String, Product>) -> (Vec<(String, f32)>, Vec<String>) {
+    let mut items = Vec::new();
+    let mut unknown = Vec::new();
+
+    for segment in line.split(',') {
+        let segment = segment.trim();
+        if segment.is_empty() {
+            continue;
+        }
+
+        let mut parts = segment.splitn(3, ' ');
+        let quantity = parts.next().and_then(|q| q.parse::<f32>().ok());
+        let unit = parts.next();
+        let product_name = parts.next().map(str::trim).filter(|n| !n.is_empty());
+
+        match (quantity, unit, product_name) {
+            (Some(quantity), Some(unit), Some(product_name)) => match products.get(product_name) {
+                Some(product) => {
+                    let from_unit = normalize_unit(unit);
+                    let to_unit = normalize_unit(&product.unit);
+                    match convert_quantity(quantity, &from_unit, &to_unit) {
+                        Some(converted) => items.push((product_name.to_string(), converted)),
+                        None => unknown.push(format!(
+                            "{} (couldn't convert {} to {})",
+                            segment, unit, product.unit
+                        )),
+                    }
+                }
+                None => unknown.push(product_name.to_string()),
+            },
+            _ => unknown.push(segment.to_string()),
+        }
+    }
+
+    (items, unknown)
+}
+
+fn read_meal_items(products: &HashMap<String, Product>) -> Vec<(String, f32)> {
+    println!("Paste a recipe line (e.g. \"135 g plain flour, 1 tsp baking powder\"), or press Enter to add items one at a time:");
+    let mut recipe_line = String::new();
+    io::stdin().read_line(&mut recipe_line).unwrap();
+    let recipe_line = recipe_line.trim();
+
+    if !recipe_line.is_empty() {
+        let (items, unknown) = parse_recipe_line(recipe_line, products);
+        if !unknown.is_empty() {
+            println!("Skipped these segments (unknown product, unconvertible unit, or not \"<quantity> <unit> <product>\"):");
+            for segment in &unknown {
+                println!("  - {}", segment);
+            }
+        }
+        return items;
+    }
 
     let mut items = Vec::new();
     loop {
@@ -281,6 +616,16 @@ fn add_meal(products: &HashMap<String, Product>, meals: &mut Vec<Meal>) {
 
         items.push((product_name, quantity));
     }
+    items
+}
+
+fn add_meal(products: &HashMap<String, Product>, meals: &mut Vec<Meal>) {
+    println!("Enter meal name:");
+    let mut meal_name = String::new();
+    io::stdin().read_line(&mut meal_name).unwrap();
+    let meal_name = meal_name.trim().to_string();
+
+    let items = read_meal_items(products);
 
     println!("Enter number of servings for this meal:");
     let servings: f32 = get_float_input("Invalid servings, using 1");
@@ -299,7 +644,9 @@ fn add_meal(products: &HashMap<String, Product>, meals: &mut Vec<Meal>) {
     })
     .sum();
     meals.push(meal);
-    save_meals(meals);
+    if let Err(e) = save_meals(meals) {
+        eprintln!("Warning: failed to save meals: {e}");
+    }
     println!("Meal '{}' added successfully! Total Calories: {:.2}", meal_name, _total_calories);
     // println!("Meal '{}' added successfully!", meal_name);
 }
@@ -338,7 +685,9 @@ fn log_daily_intake(username: &str, products: &HashMap<String, Product>, all_mea
     daily_logs.entry(log_key)
         .or_insert(DailyLog { date: today, meals: Vec::new() })
         .meals.push(meal);
-    save_daily_logs(daily_logs);
+    if let Err(e) = save_daily_logs(daily_logs) {
+        eprintln!("Warning: failed to save daily logs: {e}");
+    }
     println!("Meal logged successfully for today!");
 }
 
@@ -350,24 +699,7 @@ fn create_custom_meal(products: &HashMap<String, Product>) -> Meal {
     io::stdin().read_line(&mut meal_name).unwrap();
     let meal_name = meal_name.trim().to_string();
 
-    let mut items = Vec::new();
-    loop {
-        println!("Enter product name (or 'done' to finish):");
-        let mut product_name = String::new();
-        io::stdin().read_line(&mut product_name).unwrap();
-        let product_name = product_name.trim().to_string();
-        if product_name == "done" { break; }
-
-        if !products.contains_key(&product_name) {
-            println!("Product not found. Please add the product first.");
-            continue;
-        }
-
-        println!("Enter quantity:");
-        let quantity: f32 = get_float_input("Invalid quantity, using 0");
-
-        items.push((product_name, quantity));
-    }
+    let items = read_meal_items(products);
 
     println!("Enter number of servings:");
     let servings: f32 = get_float_input("Invalid servings, using 1");
@@ -380,33 +712,39 @@ fn create_custom_meal(products: &HashMap<String, Product>) -> Meal {
     }
 }
 
-fn load_meals() -> Vec<Meal> {
-    let mut meals = Vec::new();
-    if let Ok(data) = fs::read_to_string("meals.txt") {
-        let mut lines = data.lines();
-        while let Some(meal_name) = lines.next() {
-            let mut meal_items = Vec::new();
-            while let Some(line) = lines.next() {
-                if line.is_empty() { break; }
-                let parts: Vec<&str> = line.split(',').collect();
-                if parts.len() == 2 {
-                    meal_items.push((parts[0].to_string(), parts[1].parse().unwrap_or(0.0)));
-                }
-            }
-            meals.push(Meal {
-                name: meal_name.to_string(),
-                items: meal_items,
-                servings: 1.0, // Default servings
-            });
+fn load_records<T: DeserializeOwned>(path: &str) -> Result<Vec<T>, AppError> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let values: Vec<Value> = serde_json::from_str(&data)?;
+    let mut records = Vec::with_capacity(values.len());
+    for value in values {
+        match serde_json::from_value::<T>(value.clone()) {
+            Ok(record) => records.push(record),
+            Err(e) => eprintln!("Warning: skipping malformed entry in {}: {} ({})", path, e, value),
         }
     }
-    meals
+    Ok(records)
+}
+
+fn save_records<T: Serialize>(path: &str, records: &[T]) -> Result<(), AppError> {
+    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(path)?;
+    let json = serde_json::to_string_pretty(records)?;
+    file.write_all(json.as_bytes())?;
+    Ok(())
+}
+
+fn load_meals() -> Result<Vec<Meal>, AppError> {
+    load_records("meals.json")
 }
 
-fn view_daily_logs(username: &str, daily_logs: &HashMap<(String, NaiveDate), DailyLog>, products: &HashMap<String, Product>) {
+fn view_daily_logs(user: &User, daily_logs: &HashMap<(String, NaiveDate), DailyLog>, products: &HashMap<String, Product>) {
     // Sort dates in descending order
     let mut user_logs: Vec<_> = daily_logs.iter()
-        .filter(|((name, _), _)| name == username)
+        .filter(|((name, _), _)| name == &user.name)
         .collect();
     
     user_logs.sort_by(|a, b| b.0.1.cmp(&a.0.1));
@@ -416,6 +754,14 @@ fn view_daily_logs(username: &str, daily_logs: &HashMap<(String, NaiveDate), Dai
         return;
     }
 
+    println!("View as (1) detailed log or (2) weekly report table?");
+    let mut mode = String::new();
+    io::stdin().read_line(&mut mode).unwrap();
+    if mode.trim() == "2" {
+        print_weekly_report(user, &user_logs, products);
+        return;
+    }
+
     for ((_, date), log) in user_logs {
         println!("\n--- Daily Log for {} ---", date);
         
@@ -437,11 +783,108 @@ fn view_daily_logs(username: &str, daily_logs: &HashMap<(String, NaiveDate), Dai
 
         let total_calories = log.total_calories(products);
         let (total_proteins, total_minerals) = log.total_nutrients(products);
+        let calorie_target = tdee(user);
+        let calorie_delta = calorie_target - total_calories;
+        let protein_goal = protein_target(user);
 
         println!("\nTotal Daily Intake:");
         println!("Calories: {:.2}", total_calories);
         println!("Proteins: {:.2}g", total_proteins);
         println!("Minerals: {:.2}g", total_minerals);
+        println!(
+            "\nCalorie Target: {:.2} ({}: {:.2})",
+            calorie_target,
+            if calorie_delta >= 0.0 { "remaining" } else { "surplus" },
+            calorie_delta.abs()
+        );
+        println!("Protein Target: {:.2}g", protein_goal);
+    }
+}
+
+fn daily_totals(
+    user_logs: &[(&(String, NaiveDate), &DailyLog)],
+    products: &HashMap<String, Product>,
+) -> Vec<(NaiveDate, f32, f32, f32)> {
+    let mut days: Vec<(NaiveDate, f32, f32, f32)> = user_logs
+        .iter()
+        .map(|((_, date), log)| {
+            let calories = log.total_calories(products);
+            let (proteins, minerals) = log.total_nutrients(products);
+            (*date, calories, proteins, minerals)
+        })
+        .collect();
+    days.sort_by_key(|(date, ..)| *date);
+    if days.len() > 7 {
+        days = days.split_off(days.len() - 7);
+    }
+    days
+}
+
+fn weekly_report_rows(days: &[(NaiveDate, f32, f32, f32)]) -> Vec<[String; 4]> {
+    let mut rows: Vec<[String; 4]> = days
+        .iter()
+        .map(|(date, calories, proteins, minerals)| {
+            [
+                date.format("%Y-%m-%d").to_string(),
+                format!("{:.2}", calories),
+                format!("{:.2}", proteins),
+                format!("{:.2}", minerals),
+            ]
+        })
+        .collect();
+
+    let count = days.len() as f32;
+    let total_calories: f32 = days.iter().map(|(_, c, _, _)| c).sum();
+    let total_proteins: f32 = days.iter().map(|(_, _, p, _)| p).sum();
+    let total_minerals: f32 = days.iter().map(|(_, _, _, m)| m).sum();
+
+    rows.push([
+        "7-Day Average".to_string(),
+        format!("{:.2}", total_calories / count),
+        format!("{:.2}", total_proteins / count),
+        format!("{:.2}", total_minerals / count),
+    ]);
+    rows.push([
+        "Weekly Total".to_string(),
+        format!("{:.2}", total_calories),
+        format!("{:.2}", total_proteins),
+        format!("{:.2}", total_minerals),
+    ]);
+    rows
+}
+
+fn column_widths(header: [&str; 4], rows: &[[String; 4]]) -> [usize; 4] {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    widths
+}
+
+fn print_weekly_report(
+    user: &User,
+    user_logs: &[(&(String, NaiveDate), &DailyLog)],
+    products: &HashMap<String, Product>,
+) {
+    let days = daily_totals(user_logs, products);
+    let header = ["Date", "Calories", "Proteins", "Minerals"];
+    let rows = weekly_report_rows(&days);
+    let widths = column_widths(header, &rows);
+
+    println!("\n--- Weekly Report for {} ---", user.name);
+    println!(
+        "{:<w0$} | {:<w1$} | {:<w2$} | {:<w3$}",
+        header[0], header[1], header[2], header[3],
+        w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]
+    );
+    for row in &rows {
+        println!(
+            "{:<w0$} | {:<w1$} | {:<w2$} | {:<w3$}",
+            row[0], row[1], row[2], row[3],
+            w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]
+        );
     }
 }
 
@@ -459,117 +902,188 @@ fn get_float_input(error_msg: &str) -> f32 {
     }
 }
 
-fn load_daily_logs() -> HashMap<(String, NaiveDate), DailyLog> {
-    let mut daily_logs = HashMap::new();
-    if let Ok(data) = fs::read_to_string("daily_logs.txt") {
-        for line in data.lines() {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() >= 3 {
-                let username = parts[0].to_string();
-                let date = NaiveDate::parse_from_str(parts[1], "%Y-%m-%d").unwrap();
-                let servings = parts[2].parse::<f32>().unwrap_or(1.0);
-                let product_name = parts[3].to_string();
-                let quantity = parts[4].parse::<f32>().unwrap_or(0.0);
-
-                let log_key = (username.clone(), date);
-                daily_logs.entry(log_key)
-                    .or_insert(DailyLog { date, meals: Vec::new() })
-                    .meals.push(Meal {
-                        name: "Loaded Meal".to_string(),
-                        items: vec![(product_name, quantity)],
-                        servings,
-                    });
-            }
-        }
-    }
-    daily_logs
+fn load_daily_logs() -> Result<HashMap<(String, NaiveDate), DailyLog>, AppError> {
+    let records: Vec<DailyLogRecord> = load_records("daily_logs.json")?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let key = (record.username, record.date);
+            let log = DailyLog { date: record.date, meals: record.meals };
+            (key, log)
+        })
+        .collect())
 }
 
-fn save_daily_logs(daily_logs: &HashMap<(String, NaiveDate), DailyLog>) {
-    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open("daily_logs.txt").unwrap();
-    for ((username, date), log) in daily_logs {
-        for meal in &log.meals {
-            for (product_name, quantity) in &meal.items {
-                writeln!(file, "{},{},{},{},{}", 
-                    username, 
-                    date.format("%Y-%m-%d"), 
-                    meal.servings, 
-                    product_name, 
-                    quantity
-                ).unwrap();
-            }
-        }
-    }
+fn save_daily_logs(daily_logs: &HashMap<(String, NaiveDate), DailyLog>) -> Result<(), AppError> {
+    let records: Vec<DailyLogRecord> = daily_logs
+        .iter()
+        .map(|((username, date), log)| DailyLogRecord {
+            username: username.clone(),
+            date: *date,
+            meals: log.meals.clone(),
+        })
+        .collect();
+
+    save_records("daily_logs.json", &records)
 }
 
+fn load_users() -> Result<HashMap<String, User>, AppError> {
+    let users: Vec<User> = load_records("users.json")?;
+    Ok(users.into_iter().map(|user| (user.name.clone(), user)).collect())
+}
 
+fn save_users(users: &HashMap<String, User>) -> Result<(), AppError> {
+    let users: Vec<&User> = users.values().collect();
+    save_records("users.json", &users)
+}
 
-fn load_users() -> HashMap<String, User> {
-    let mut users = HashMap::new();
-    if let Ok(data) = fs::read_to_string("users.txt") {
-        for line in data.lines() {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() == 5 {
-                let user = User {
-                    name: parts[0].to_string(),
-                    age: parts[1].parse().unwrap_or(0),
-                    sex: parts[2].to_string(),
-                    height: parts[3].parse().unwrap_or(0.0),
-                    weight: parts[4].parse().unwrap_or(0.0),
-                };
-                users.insert(user.name.clone(), user);
-            }
-        }
-    }
-    users
+fn save_meals(meals: &Vec<Meal>) -> Result<(), AppError> {
+    save_records("meals.json", meals)
 }
 
-fn save_users(users: &HashMap<String, User>) {
-    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open("users.txt").unwrap();
-    for user in users.values() {
-        writeln!(file, "{},{},{},{},{}", user.name, user.age, user.sex, user.height, user.weight).unwrap();
-    }
+fn load_products() -> Result<HashMap<String, Product>, AppError> {
+    let records: Vec<ProductRecord> = load_records("products.json")?;
+    Ok(records.into_iter().map(|record| (record.name, record.product)).collect())
 }
 
-fn save_meals(meals: &Vec<Meal>) {
-    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open("meals.txt").unwrap();
-    for meal in meals {
-        writeln!(file, "{}", meal.name).unwrap();
-        for (product_name, quantity) in &meal.items {
-            writeln!(file, "{},{}", product_name, quantity).unwrap();
-        }
-        writeln!(file, "").unwrap();
-    }
+fn save_products(products: &HashMap<String, Product>) -> Result<(), AppError> {
+    let records: Vec<ProductRecord> = products
+        .iter()
+        .map(|(name, product)| ProductRecord { name: name.clone(), product: product.clone() })
+        .collect();
+
+    save_records("products.json", &records)
 }
 
-fn load_products() -> HashMap<String, Product> {
-    let mut products = HashMap::new();
-    if let Ok(data) = fs::read_to_string("products.txt") {
-        for line in data.lines() {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() == 5 {
-                let key = parts[0].to_string();
-                let product = Product {
-                    unit: parts[1].to_string(),
-                    calories: parts[2].parse().unwrap_or(0.0),
-                    proteins: parts[3].parse().unwrap_or(0.0),
-                    minerals: parts[4].parse().unwrap_or(0.0),
-                };
-                products.insert(key, product);
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(unit: &str) -> Product {
+        Product { unit: unit.to_string(), calories: 1.0, proteins: 1.0, minerals: 1.0 }
+    }
+
+    fn user(sex: &str, age: u32, height: f32, weight: f32, activity_level: ActivityLevel) -> User {
+        User { name: "test".to_string(), age, sex: sex.to_string(), height, weight, activity_level }
+    }
+
+    #[test]
+    fn bmr_male_matches_mifflin_st_jeor() {
+        let u = user("M", 30, 180.0, 80.0, ActivityLevel::Sedentary);
+        assert!((bmr(&u) - 1780.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn bmr_female_matches_mifflin_st_jeor() {
+        let u = user("F", 30, 165.0, 60.0, ActivityLevel::Sedentary);
+        assert!((bmr(&u) - 1320.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn tdee_scales_bmr_by_activity_factor() {
+        let u = user("M", 30, 180.0, 80.0, ActivityLevel::Active);
+        assert!((tdee(&u) - bmr(&u) * 1.725).abs() < 0.01);
+    }
+
+    #[test]
+    fn protein_target_is_0_8_grams_per_kg() {
+        let u = user("F", 25, 170.0, 70.0, ActivityLevel::Light);
+        assert!((protein_target(&u) - 56.0).abs() < 0.01);
+    }
+
+    fn empty_log(date: NaiveDate) -> DailyLog {
+        DailyLog { date, meals: Vec::new() }
+    }
+
+    #[test]
+    fn daily_totals_truncates_to_last_7_days() {
+        let keys: Vec<(String, NaiveDate)> = (1..=10)
+            .map(|d| ("test".to_string(), NaiveDate::from_ymd_opt(2026, 1, d).unwrap()))
+            .collect();
+        let logs: Vec<DailyLog> = keys.iter().map(|(_, date)| empty_log(*date)).collect();
+        let user_logs: Vec<_> = keys.iter().zip(logs.iter()).collect();
+        let products = HashMap::new();
+
+        let days = daily_totals(&user_logs, &products);
+
+        assert_eq!(days.len(), 7);
+        assert_eq!(days.first().unwrap().0, NaiveDate::from_ymd_opt(2026, 1, 4).unwrap());
+        assert_eq!(days.last().unwrap().0, NaiveDate::from_ymd_opt(2026, 1, 10).unwrap());
+    }
+
+    #[test]
+    fn weekly_report_rows_appends_average_and_total_footer() {
+        let days = vec![
+            (NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), 2000.0, 100.0, 50.0),
+            (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 1000.0, 50.0, 30.0),
+        ];
+
+        let rows = weekly_report_rows(&days);
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[2], ["7-Day Average".to_string(), "1500.00".to_string(), "75.00".to_string(), "40.00".to_string()]);
+        assert_eq!(rows[3], ["Weekly Total".to_string(), "3000.00".to_string(), "150.00".to_string(), "80.00".to_string()]);
+    }
+
+    #[test]
+    fn column_widths_grows_to_fit_longest_cell() {
+        let header = ["Date", "Calories", "Proteins", "Minerals"];
+        let rows = vec![["2026-01-01".to_string(), "1.00".to_string(), "1.00".to_string(), "1.00".to_string()]];
+
+        let widths = column_widths(header, &rows);
+
+        assert_eq!(widths, [10, "Calories".len(), "Proteins".len(), "Minerals".len()]);
+    }
+
+    #[test]
+    fn parse_recipe_line_matches_unit_exactly() {
+        let products = HashMap::from([("plain flour".to_string(), product("g"))]);
+        let (items, unknown) = parse_recipe_line("135 g plain flour", &products);
+        assert_eq!(items, vec![("plain flour".to_string(), 135.0)]);
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn parse_recipe_line_converts_g_to_100g() {
+        let products = HashMap::from([("brown sugar".to_string(), product("100g"))]);
+        let (items, unknown) = parse_recipe_line("250 g brown sugar", &products);
+        assert_eq!(items, vec![("brown sugar".to_string(), 2.5)]);
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn parse_recipe_line_converts_tablespoon_to_100g() {
+        let products = HashMap::from([("brown sugar".to_string(), product("100g"))]);
+        let (items, unknown) = parse_recipe_line("2 tbsp brown sugar", &products);
+        assert_eq!(items, vec![("brown sugar".to_string(), 0.3)]);
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn parse_recipe_line_flags_unconvertible_unit() {
+        let products = HashMap::from([("scallions".to_string(), product("bunch"))]);
+        let (items, unknown) = parse_recipe_line("2 cup scallions", &products);
+        assert!(items.is_empty());
+        assert_eq!(unknown.len(), 1);
+        assert!(unknown[0].contains("couldn't convert"));
+    }
+
+    #[test]
+    fn parse_recipe_line_flags_unknown_product() {
+        let products = HashMap::new();
+        let (items, unknown) = parse_recipe_line("1 tsp mystery spice", &products);
+        assert!(items.is_empty());
+        assert_eq!(unknown, vec!["mystery spice".to_string()]);
+    }
+
+    #[test]
+    fn convert_quantity_same_unit_is_identity() {
+        assert_eq!(convert_quantity(5.0, "cup", "cup"), Some(5.0));
     }
-    products
-}
 
-fn save_products(products: &HashMap<String, Product>) {
-    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open("products.txt").unwrap();
-    for (key, product) in products.iter() {
-        writeln!(
-            file,
-            "{},{},{},{},{}",
-            key, product.unit, product.calories, product.proteins, product.minerals
-        )
-        .unwrap();
+    #[test]
+    fn convert_quantity_unknown_unit_is_none() {
+        assert_eq!(convert_quantity(5.0, "bunch", "g"), None);
     }
 }